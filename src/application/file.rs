@@ -0,0 +1,184 @@
+//! File-backed store for job attempts that haven't been durably committed to Redis yet, and for
+//! attempts that have exhausted their retries.
+//!
+//! `handlers::queue::create_job` writes a `CreateRequest` here before submitting it, so a dropped
+//! Redis connection doesn't lose the caller's request; `reattempt_job` reads it back for a manual
+//! retry. `retry::on_job_failed` writes failed attempts back under a computed next-attempt
+//! timestamp (or into the dead-letter store once retries are exhausted), and `retry::run_scheduler`
+//! / `handlers::queue::dead_job_ids`/`dead_requeue` read them back out.
+//!
+//! Each attempt is one JSON file, named by its timestamp key, under a per-queue directory rooted
+//! at [`BASE_DIR`]; dead-lettered attempts live in a `dead` subdirectory of the same queue
+//! directory.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::models::{job, OcyError};
+
+/// Root directory job attempts are written under.
+const BASE_DIR: &str = "data/job_attempts";
+
+fn unix_now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+fn queue_dir(queue_name: &str) -> PathBuf {
+    Path::new(BASE_DIR).join(queue_name)
+}
+
+fn dead_letter_dir(queue_name: &str) -> PathBuf {
+    queue_dir(queue_name).join("dead")
+}
+
+fn attempt_path(queue_name: &str, timestamp: i64) -> PathBuf {
+    queue_dir(queue_name).join(format!("{}.json", timestamp))
+}
+
+fn dead_letter_path(queue_name: &str, timestamp: i64) -> PathBuf {
+    dead_letter_dir(queue_name).join(format!("{}.json", timestamp))
+}
+
+fn timestamp_from_path(path: &Path) -> Option<i64> {
+    path.file_stem()?.to_str()?.parse().ok()
+}
+
+/// An attempt as persisted on disk: the request it was submitted with, and which attempt number
+/// this is. `retry::on_job_failed`/`reattempt_job` need this alongside the bare `CreateRequest`
+/// because every retry is re-submitted through `Storage::create_job` under a brand-new job id -
+/// without persisting the count here it would reset to zero on every retry, and the dead-letter
+/// threshold in `retry::on_job_failed` would never trip.
+#[derive(serde::Serialize)]
+struct StoredAttemptRef<'a> {
+    job_req: &'a job::CreateRequest,
+    attempt: u64,
+}
+
+#[derive(serde::Deserialize)]
+struct StoredAttempt {
+    job_req: job::CreateRequest,
+    attempt: u64,
+}
+
+/// Persists `job_req` as a pending, first-time attempt (`attempt` 0) for `queue_name`, keyed by
+/// the current unix timestamp. Returns `(created_at, timestamp)` - the second element is the key
+/// later passed to `get_job`/`delete_job`.
+pub fn write_job(queue_name: &str, job_req: &job::CreateRequest) -> io::Result<(i64, i64)> {
+    let now = unix_now();
+    write_job_at(queue_name, job_req, now, 0)?;
+    Ok((now, now))
+}
+
+/// Persists `job_req` as a pending attempt for `queue_name`, keyed by an explicit timestamp rather
+/// than "now" - used by `retry::on_job_failed` to write a failed attempt back with its computed
+/// backoff delay already applied, so `due_attempts` won't surface it until that time passes.
+/// `attempt` is the attempt number this retry will be when it's next re-submitted.
+pub fn write_job_at(
+    queue_name: &str,
+    job_req: &job::CreateRequest,
+    timestamp: i64,
+    attempt: u64,
+) -> io::Result<()> {
+    let dir = queue_dir(queue_name);
+    fs::create_dir_all(&dir)?;
+    let stored = StoredAttemptRef { job_req, attempt };
+    fs::write(attempt_path(queue_name, timestamp), serde_json::to_vec(&stored)?)
+}
+
+/// Reads back a pending attempt written by `write_job`/`write_job_at`, as `(job_req, attempt)`.
+pub fn get_job(queue_name: &str, timestamp: i64) -> Result<(job::CreateRequest, u64), OcyError> {
+    let bytes = fs::read(attempt_path(queue_name, timestamp))
+        .map_err(|err| OcyError::Internal(err.to_string()))?;
+    let stored: StoredAttempt =
+        serde_json::from_slice(&bytes).map_err(|err| OcyError::Internal(err.to_string()))?;
+    Ok((stored.job_req, stored.attempt))
+}
+
+/// Deletes a pending attempt once it's no longer needed (successfully enqueued, or dead-lettered).
+pub fn delete_job(queue_name: &str, timestamp: i64) -> io::Result<()> {
+    match fs::remove_file(attempt_path(queue_name, timestamp)) {
+        Ok(()) => Ok(()),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(()),
+        Err(err) => Err(err),
+    }
+}
+
+/// Returns the timestamps of every pending attempt for `queue_name` that is due - i.e. whose
+/// timestamp is `<= now` - oldest first, for `retry::run_scheduler` to re-submit.
+pub fn due_attempts(queue_name: &str, now: i64) -> io::Result<Vec<i64>> {
+    let dir = queue_dir(queue_name);
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut due = Vec::new();
+    for entry in fs::read_dir(&dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            continue;
+        }
+        if let Some(timestamp) = timestamp_from_path(&path) {
+            if timestamp <= now {
+                due.push(timestamp);
+            }
+        }
+    }
+    due.sort_unstable();
+    Ok(due)
+}
+
+/// Moves a failed attempt into the dead-letter store once its retries are exhausted, recording
+/// the attempt count it died at. Returns `(created_at, timestamp)`, mirroring `write_job`.
+pub fn write_dead_letter(queue_name: &str, job_req: &job::CreateRequest, attempt: u64) -> io::Result<(i64, i64)> {
+    let now = unix_now();
+    let dir = dead_letter_dir(queue_name);
+    fs::create_dir_all(&dir)?;
+    let stored = StoredAttemptRef { job_req, attempt };
+    fs::write(dead_letter_path(queue_name, now), serde_json::to_vec(&stored)?)?;
+    Ok((now, now))
+}
+
+/// Lists every dead-lettered attempt for `queue_name`, oldest first, as `(timestamp, job_req,
+/// attempt)`.
+pub fn list_dead_letters(queue_name: &str) -> io::Result<Vec<(i64, job::CreateRequest, u64)>> {
+    let dir = dead_letter_dir(queue_name);
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut entries = Vec::new();
+    for entry in fs::read_dir(&dir)? {
+        let path = entry?.path();
+        let Some(timestamp) = timestamp_from_path(&path) else {
+            continue;
+        };
+        if let Ok(stored) = serde_json::from_slice::<StoredAttempt>(&fs::read(&path)?) {
+            entries.push((timestamp, stored.job_req, stored.attempt));
+        }
+    }
+    entries.sort_unstable_by_key(|(timestamp, ..)| *timestamp);
+    Ok(entries)
+}
+
+/// Reads back a single dead-lettered attempt by its timestamp, as `(job_req, attempt)`.
+pub fn get_dead_letter(queue_name: &str, timestamp: i64) -> Result<(job::CreateRequest, u64), OcyError> {
+    let bytes = fs::read(dead_letter_path(queue_name, timestamp))
+        .map_err(|err| OcyError::Internal(err.to_string()))?;
+    let stored: StoredAttempt =
+        serde_json::from_slice(&bytes).map_err(|err| OcyError::Internal(err.to_string()))?;
+    Ok((stored.job_req, stored.attempt))
+}
+
+/// Removes a dead-lettered attempt, e.g. once `handlers::queue::dead_requeue` has re-submitted it.
+pub fn delete_dead_letter(queue_name: &str, timestamp: i64) -> io::Result<()> {
+    match fs::remove_file(dead_letter_path(queue_name, timestamp)) {
+        Ok(()) => Ok(()),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(()),
+        Err(err) => Err(err),
+    }
+}
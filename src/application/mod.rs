@@ -0,0 +1,5 @@
+//! Application-level services shared by the HTTP handlers: the core Redis job/queue data model
+//! (`RedisManager`, defined alongside the rest of the data model) and the file-backed attempt
+//! store used for write-ahead durability and retries (`file`).
+
+pub mod file;
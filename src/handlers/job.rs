@@ -0,0 +1,133 @@
+//! HTTP handlers for the `/job` endpoints.
+
+use actix_web::{web, HttpResponse, Responder, ResponseError};
+use log::error;
+
+use crate::models::{ApplicationState, OcyError};
+use crate::notifier::JobEvent;
+use crate::retry;
+
+/// Body of a `POST /job/{id}/heartbeat` request.
+#[derive(Debug, serde::Deserialize)]
+pub struct HeartbeatRequest {
+    /// Identifies the worker claiming to still be processing this job; must match the
+    /// `runner_id` it was handed the job under.
+    runner_id: String,
+}
+
+/// Handles `POST /job/{id}/heartbeat` requests. Workers call this periodically while processing
+/// a claimed job to prove they're still alive - `monitor::run` reclaims jobs whose heartbeat goes
+/// stale back onto their queue, or fails them once retries are exhausted.
+///
+/// # Returns
+///
+/// * 204 - heartbeat recorded.
+/// * 404 - no such job, or the job is not currently owned by `runner_id`.
+pub async fn heartbeat(
+    path: web::Path<u64>,
+    json: web::Json<HeartbeatRequest>,
+    data: web::Data<ApplicationState>,
+) -> impl Responder {
+    let job_id = path.into_inner();
+    let runner_id = json.into_inner().runner_id;
+
+    match data.storage.heartbeat(job_id, &runner_id).await {
+        Ok(()) => HttpResponse::NoContent().finish(),
+        Err(err @ OcyError::NoSuchJob(_)) => err.error_response(),
+        Err(err) => {
+            error!("[job:{}] failed to record heartbeat: {}", job_id, err);
+            err.error_response()
+        }
+    }
+}
+
+/// Body of a `POST /job/{id}/complete` request.
+#[derive(Debug, serde::Deserialize)]
+pub struct CompleteRequest {
+    /// Must match the `runner_id` the job was claimed under.
+    runner_id: String,
+    /// Output produced by the job, stored alongside it and included in the webhook payload.
+    output: Option<serde_json::Value>,
+}
+
+/// Handles `POST /job/{id}/complete` requests, marking a job owned by `runner_id` as done and
+/// notifying any configured webhook targets.
+pub async fn complete(
+    path: web::Path<u64>,
+    json: web::Json<CompleteRequest>,
+    data: web::Data<ApplicationState>,
+) -> impl Responder {
+    let job_id = path.into_inner();
+    let req = json.into_inner();
+
+    match data.storage.complete(job_id, &req.runner_id, req.output).await {
+        Ok((queue_name, job)) => {
+            data.notifier.notify(JobEvent::Completed, &queue_name, &job);
+            HttpResponse::NoContent().finish()
+        }
+        Err(err @ OcyError::NoSuchJob(_)) => err.error_response(),
+        Err(err) => {
+            error!("[job:{}] failed to mark job complete: {}", job_id, err);
+            err.error_response()
+        }
+    }
+}
+
+/// Body of a `POST /job/{id}/fail` request.
+#[derive(Debug, serde::Deserialize)]
+pub struct FailRequest {
+    /// Must match the `runner_id` the job was claimed under.
+    runner_id: String,
+    /// Human-readable description of what went wrong, stored alongside the job.
+    error_msg: Option<String>,
+}
+
+/// Handles `POST /job/{id}/fail` requests, marking a job owned by `runner_id` as failed and
+/// notifying any configured webhook targets.
+pub async fn fail(
+    path: web::Path<u64>,
+    json: web::Json<FailRequest>,
+    data: web::Data<ApplicationState>,
+) -> impl Responder {
+    let job_id = path.into_inner();
+    let req = json.into_inner();
+
+    match data.storage.fail(job_id, &req.runner_id, req.error_msg).await {
+        Ok((queue_name, job)) => {
+            data.notifier.notify(JobEvent::Failed, &queue_name, &job);
+            schedule_retry_or_dead_letter(&data, &queue_name, &job).await;
+            HttpResponse::NoContent().finish()
+        }
+        Err(err @ OcyError::NoSuchJob(_)) => err.error_response(),
+        Err(err) => {
+            error!("[job:{}] failed to mark job failed: {}", job_id, err);
+            err.error_response()
+        }
+    }
+}
+
+/// Looks up `queue_name`'s retry settings and hands the failed job off to [`retry::on_job_failed`]
+/// to either schedule it for automatic retry or move it to the dead-letter store.
+async fn schedule_retry_or_dead_letter(
+    data: &ApplicationState,
+    queue_name: &str,
+    job: &crate::models::job::Payload,
+) {
+    let settings = match data.storage.queue_settings(queue_name).await {
+        Ok(settings) => settings,
+        Err(err) => {
+            error!(
+                "[queue:{}] failed to load settings to schedule retry for job {}: {}",
+                queue_name, job.id, err
+            );
+            return;
+        }
+    };
+
+    if let Err(err) = retry::on_job_failed(queue_name, &settings, job) {
+        error!(
+            "[queue:{}] failed to schedule retry/dead-letter for job {}: {}",
+            queue_name, job.id, err
+        );
+    }
+}
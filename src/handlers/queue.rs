@@ -3,8 +3,131 @@
 use actix_web::{web, HttpResponse, Responder,  ResponseError};
 use log::{debug, error};
 
-use crate::application::{RedisManager, file};
+use crate::application::file;
 use crate::models::{job, queue, ApplicationState, OcyError};
+use crate::notifier::JobEvent;
+
+/// Query parameters accepted by read handlers that support field projection via `?fields=`, and
+/// by `next_job`/`fetch_job` for long-polling via `?wait=`.
+#[derive(Debug, serde::Deserialize)]
+pub struct QueueFields {
+    /// Comma-separated list of field names to include in the response. The full object is
+    /// returned when this is omitted.
+    fields: Option<String>,
+    /// Seconds to hold the connection open waiting for a job when the queue is currently empty,
+    /// instead of immediately falling back to the configured `next_job_delay` sleep-and-204.
+    /// Absent or zero preserves the existing behaviour.
+    wait: Option<u64>,
+}
+
+impl QueueFields {
+    /// Splits `fields` into trimmed, non-empty field names, or `None` if no projection was
+    /// requested.
+    fn names(&self) -> Option<Vec<&str>> {
+        self.fields.as_deref().map(|raw| {
+            raw.split(',')
+                .map(str::trim)
+                .filter(|name| !name.is_empty())
+                .collect()
+        })
+    }
+}
+
+/// Field names that may be requested via `?fields=` against `queue::Settings` responses.
+const QUEUE_SETTINGS_FIELDS: &[&str] = &[
+    "timeout",
+    "heartbeat_timeout",
+    "expires_after",
+    "retries",
+    "retry_delays",
+];
+
+/// Field names that may be requested via `?fields=` against job responses.
+const JOB_FIELDS: &[&str] = &[
+    "id",
+    "queue",
+    "status",
+    "input",
+    "output",
+    "tags",
+    "created_at",
+    "started_at",
+    "ended_at",
+    "runner_id",
+];
+
+/// Serializes `value` and, if `query` names a projection, reduces the result down to just the
+/// requested fields. Returns `OcyError::BadRequest` if a name outside `known_fields` is requested.
+fn project_fields<T: serde::Serialize>(
+    value: &T,
+    query: &QueueFields,
+    known_fields: &[&str],
+) -> Result<serde_json::Value, OcyError> {
+    let full = serde_json::to_value(value)
+        .map_err(|err| OcyError::Internal(err.to_string()))?;
+    let names = match query.names() {
+        Some(names) => names,
+        None => return Ok(full),
+    };
+
+    let obj = match full {
+        serde_json::Value::Object(obj) => obj,
+        other => return Ok(other),
+    };
+
+    let mut reduced = serde_json::Map::with_capacity(names.len());
+    for name in names {
+        if !known_fields.contains(&name) {
+            return Err(OcyError::BadRequest(format!("unknown field: {}", name)));
+        }
+        if let Some(field_value) = obj.get(name) {
+            reduced.insert(name.to_owned(), field_value.clone());
+        }
+    }
+    Ok(serde_json::Value::Object(reduced))
+}
+
+#[cfg(test)]
+mod project_fields_tests {
+    use super::*;
+
+    #[derive(serde::Serialize)]
+    struct Widget {
+        id: u64,
+        name: String,
+        color: String,
+    }
+
+    const WIDGET_FIELDS: &[&str] = &["id", "name", "color"];
+
+    fn query(fields: Option<&str>) -> QueueFields {
+        QueueFields {
+            fields: fields.map(str::to_owned),
+            wait: None,
+        }
+    }
+
+    #[test]
+    fn no_fields_query_returns_the_full_object() {
+        let widget = Widget { id: 1, name: "cog".to_owned(), color: "red".to_owned() };
+        let projected = project_fields(&widget, &query(None), WIDGET_FIELDS).unwrap();
+        assert_eq!(projected, serde_json::json!({"id": 1, "name": "cog", "color": "red"}));
+    }
+
+    #[test]
+    fn fields_query_reduces_to_the_requested_subset() {
+        let widget = Widget { id: 1, name: "cog".to_owned(), color: "red".to_owned() };
+        let projected = project_fields(&widget, &query(Some(" id , color ")), WIDGET_FIELDS).unwrap();
+        assert_eq!(projected, serde_json::json!({"id": 1, "color": "red"}));
+    }
+
+    #[test]
+    fn unknown_field_is_rejected() {
+        let widget = Widget { id: 1, name: "cog".to_owned(), color: "red".to_owned() };
+        let err = project_fields(&widget, &query(Some("id,weight")), WIDGET_FIELDS).unwrap_err();
+        assert!(matches!(err, OcyError::BadRequest(_)));
+    }
+}
 
 /// Handle `GET /queue` requests to get a JSON list of all existing queues.
 ///
@@ -12,12 +135,7 @@ use crate::models::{job, queue, ApplicationState, OcyError};
 ///
 /// * 200 - JSON response containing list of queue names.
 pub async fn index(data: web::Data<ApplicationState>) -> impl Responder {
-    let mut conn = match data.redis_conn_pool.get().await {
-        Ok(conn) => conn,
-        Err(err) => return OcyError::RedisConnection(err).error_response(),
-    };
-
-    match RedisManager::queue_names(&mut conn).await {
+    match data.storage.queue_names().await {
         Ok(queue_names) => HttpResponse::Ok().json(queue_names),
         Err(err) => {
             error!("Failed to fetch queue names: {}", err);
@@ -34,12 +152,8 @@ pub async fn create_or_update(
 ) -> impl Responder {
     let queue_name = path.into_inner();
     let queue_settings = json.into_inner();
-    let mut conn = match data.redis_conn_pool.get().await {
-        Ok(conn) => conn,
-        Err(err) => return OcyError::RedisConnection(err).error_response(),
-    };
 
-    match RedisManager::create_or_update_queue(&mut conn, &queue_name, &queue_settings).await {
+    match data.storage.create_or_update_queue(&queue_name, &queue_settings).await {
         Ok(true) => HttpResponse::Created()
             .append_header(("Location", format!("/queue/{}", queue_name)))
             .finish(),
@@ -57,12 +171,8 @@ pub async fn create_or_update(
 
 pub async fn delete(path: web::Path<String>, data: web::Data<ApplicationState>) -> impl Responder {
     let queue_name = path.into_inner();
-    let mut conn = match data.redis_conn_pool.get().await {
-        Ok(conn) => conn,
-        Err(err) => return OcyError::RedisConnection(err).error_response(),
-    };
 
-    match RedisManager::delete_queue(&mut conn, &queue_name).await {
+    match data.storage.delete_queue(&queue_name).await {
         Ok(true) => HttpResponse::NoContent().reason("Queue deleted").finish(),
         Ok(false) => HttpResponse::NotFound().reason("Queue not found").finish(),
         Err(err @ OcyError::BadRequest(_)) => err.error_response(),
@@ -75,15 +185,16 @@ pub async fn delete(path: web::Path<String>, data: web::Data<ApplicationState>)
 
 pub async fn settings(
     path: web::Path<String>,
+    query: web::Query<QueueFields>,
     data: web::Data<ApplicationState>,
 ) -> impl Responder {
     let queue_name = path.into_inner();
-    let mut conn = match data.redis_conn_pool.get().await {
-        Ok(conn) => conn,
-        Err(err) => return OcyError::RedisConnection(err).error_response(),
-    };
-    match RedisManager::queue_settings(&mut conn, &queue_name).await {
-        Ok(summary) => HttpResponse::Ok().json(summary),
+
+    match data.storage.queue_settings(&queue_name).await {
+        Ok(summary) => match project_fields(&summary, &query, QUEUE_SETTINGS_FIELDS) {
+            Ok(projected) => HttpResponse::Ok().json(projected),
+            Err(err) => err.error_response(),
+        },
         Err(err @ OcyError::NoSuchQueue(_)) => err.error_response(),
         Err(err) => {
             error!(
@@ -95,13 +206,12 @@ pub async fn settings(
     }
 }
 
+/// Handles `GET /queue/{queue_name}/size` requests. The response is already a single integer, so
+/// `?fields=` has nothing to project and is accepted but ignored.
 pub async fn size(path: web::Path<String>, data: web::Data<ApplicationState>) -> impl Responder {
     let queue_name = path.into_inner();
-    let mut conn = match data.redis_conn_pool.get().await {
-        Ok(conn) => conn,
-        Err(err) => return OcyError::RedisConnection(err).error_response(),
-    };
-    match RedisManager::queue_size(&mut conn, &queue_name).await {
+
+    match data.storage.queue_size(&queue_name).await {
         Ok(size) => HttpResponse::Ok().json(size),
         Err(err @ OcyError::NoSuchQueue(_)) => err.error_response(),
         Err(err) => {
@@ -114,13 +224,12 @@ pub async fn size(path: web::Path<String>, data: web::Data<ApplicationState>) ->
     }
 }
 
+/// Handles `GET /queue/{queue_name}/job_ids` requests. The response is already a bare list of
+/// ids, so `?fields=` has nothing to project and is accepted but ignored.
 pub async fn job_ids(path: web::Path<String>, data: web::Data<ApplicationState>) -> impl Responder {
     let queue_name = path.into_inner();
-    let mut conn = match data.redis_conn_pool.get().await {
-        Ok(conn) => conn,
-        Err(err) => return OcyError::RedisConnection(err).error_response(),
-    };
-    match RedisManager::queue_job_ids(&mut conn, &queue_name).await {
+
+    match data.storage.queue_job_ids(&queue_name).await {
         Ok(size) => HttpResponse::Ok().json(size),
         Err(err @ OcyError::NoSuchQueue(_)) => err.error_response(),
         Err(err) => {
@@ -140,21 +249,27 @@ pub async fn create_job(
 ) -> impl Responder {
     let queue_name = path.into_inner();
     let job_req = json.into_inner();
-    let mut conn = match data.redis_conn_pool.get().await {
-        Ok(conn) => conn,
-        Err(err) => return OcyError::from(err).error_response(),
-    };
     let job_write_res = file::write_job(&queue_name, &job_req).unwrap();
 
-    match RedisManager::create_job(&mut conn, &queue_name, &job_req).await {
-        Ok(job_id) => {
+    match data.storage.create_job(&queue_name, &job_req).await {
+        Ok(job::CreateOutcome::Created(job_id)) => {
             let job_attempt = file::get_job(&queue_name, job_write_res.1);
             debug!("deleting job attempt {:?}", job_attempt);
             let _del = file::delete_job(&queue_name, job_write_res.1);
+            data.job_notifier.notify(&queue_name);
+            notify_job_created(&data, &queue_name, job_id).await;
             HttpResponse::Created()
                 .append_header(("Location", format!("/job/{}", job_id)))
                 .json(job_id)
         },
+        Ok(job::CreateOutcome::Existing(job_id)) => {
+            // A live job already holds this `unique_key` - report it instead of enqueuing a
+            // duplicate, and discard the file-backed attempt we just wrote for this request.
+            let _del = file::delete_job(&queue_name, job_write_res.1);
+            HttpResponse::Ok()
+                .append_header(("Location", format!("/job/{}", job_id)))
+                .json(job_id)
+        },
         Err(err @ OcyError::NoSuchQueue(_) | err @ OcyError::BadRequest(_) ) => err.error_response(),
         Err(err) => {
             error!("[queue:{}] failed to create new job: {}", &queue_name, err);
@@ -163,24 +278,50 @@ pub async fn create_job(
     }
 }
 
+/// Fetches the freshly-created `job_id` back out of storage and reports it to the configured
+/// `Notifier`. The create path only has the id to hand, not the full payload the notifier needs
+/// to build its callback body, so this costs one extra read - acceptable since it's off the hot
+/// polling path.
+async fn notify_job_created(data: &ApplicationState, queue_name: &str, job_id: u64) {
+    match data.storage.fetch_queued_job(queue_name, job_id).await {
+        Ok(Some(job)) => data.notifier.notify(JobEvent::Created, queue_name, &job),
+        Ok(None) => debug!("[queue:{}] job {} vanished before it could be notified on", queue_name, job_id),
+        Err(err) => error!("[queue:{}] failed to fetch job {} for notification: {}", queue_name, job_id, err),
+    }
+}
+
 pub async fn next_job(
     path: web::Path<String>,
+    query: web::Query<QueueFields>,
     data: web::Data<ApplicationState>,
 ) -> impl Responder {
     let queue_name = path.into_inner();
-    let mut conn = match data.redis_conn_pool.get().await {
-        Ok(conn) => conn,
-        Err(err) => return OcyError::from(err).error_response(),
-    };
+    let wait_secs = query.wait.filter(|secs| *secs > 0);
+
+    // Subscribe before the emptiness check below, not after - otherwise a job created in the gap
+    // between the check and the subscribe call is missed, and long_poll_next_job ends up waiting
+    // out the entire timeout instead of waking as soon as it arrives.
+    let receiver = wait_secs.map(|_| data.job_notifier.subscribe(&queue_name));
 
-    match RedisManager::next_queued_job(&mut conn, &queue_name).await {
-        Ok(Some(job)) => HttpResponse::Ok().json(job),
-        Ok(None) => match &data.config.server.next_job_delay {
-            Some(delay) if !delay.is_zero() => {
-                tokio::time::sleep(delay.0).await;
-                HttpResponse::NoContent().into()
+    match data.storage.next_queued_job(&queue_name).await {
+        Ok(Some(job)) => {
+            data.notifier.notify(JobEvent::Started, &queue_name, &job);
+            match project_fields(&job, &query, JOB_FIELDS) {
+                Ok(projected) => HttpResponse::Ok().json(projected),
+                Err(err) => err.error_response(),
+            }
+        }
+        Ok(None) => match (wait_secs, receiver) {
+            (Some(wait_secs), Some(receiver)) => {
+                long_poll_next_job(&data, &queue_name, &query, wait_secs, receiver).await
             }
-            _ => HttpResponse::NoContent().into(),
+            _ => match &data.config.server.next_job_delay {
+                Some(delay) if !delay.is_zero() => {
+                    tokio::time::sleep(delay.0).await;
+                    HttpResponse::NoContent().into()
+                }
+                _ => HttpResponse::NoContent().into(),
+            },
         },
         Err(err) => {
             error!("[queue:{}] failed to fetch next job: {}", &queue_name, err);
@@ -189,23 +330,72 @@ pub async fn next_job(
     }
 }
 
+/// Holds a `next_job` request open for up to `wait_secs`, re-checking the queue each time
+/// `receiver` reports a job was enqueued, rather than sleeping for a fixed delay. Used when the
+/// caller passes `?wait=` on an otherwise-empty queue. `receiver` must come from a
+/// `data.job_notifier.subscribe` call made *before* `next_job`'s initial emptiness check, so a job
+/// created in between isn't missed.
+async fn long_poll_next_job(
+    data: &ApplicationState,
+    queue_name: &str,
+    query: &QueueFields,
+    wait_secs: u64,
+    mut receiver: tokio::sync::broadcast::Receiver<()>,
+) -> HttpResponse {
+    let deadline = tokio::time::Instant::now() + std::time::Duration::from_secs(wait_secs);
+    loop {
+        let remaining = deadline.saturating_duration_since(tokio::time::Instant::now());
+        if remaining.is_zero() {
+            return HttpResponse::NoContent().into();
+        }
+        crate::notify::JobNotifier::wait(&mut receiver, remaining).await;
+        match data.storage.next_queued_job(queue_name).await {
+            Ok(Some(job)) => {
+                data.notifier.notify(JobEvent::Started, queue_name, &job);
+                return match project_fields(&job, query, JOB_FIELDS) {
+                    Ok(projected) => HttpResponse::Ok().json(projected),
+                    Err(err) => err.error_response(),
+                }
+            }
+            Ok(None) => continue,
+            Err(err) => {
+                error!("[queue:{}] failed to fetch next job: {}", queue_name, err);
+                return err.error_response();
+            }
+        }
+    }
+}
+
 pub async fn fetch_job(
     path: web::Path<(String, u64)>,
+    query: web::Query<QueueFields>,
     data: web::Data<ApplicationState>,
 ) -> impl Responder {
     let (queue_name, job_id) = path.into_inner();
-    let mut conn = match data.redis_conn_pool.get().await {
-        Ok(conn) => conn,
-        Err(err) => return OcyError::from(err).error_response(),
-    };
-    match RedisManager::fetch_queued_job(&mut conn, &queue_name, job_id).await {
-        Ok(Some(job)) => HttpResponse::Ok().json(job),
-        Ok(None) => match &data.config.server.next_job_delay {
-            Some(delay) if !delay.is_zero() => {
-                tokio::time::sleep(delay.0).await;
-                HttpResponse::NoContent().into()
+    let wait_secs = query.wait.filter(|secs| *secs > 0);
+
+    // Subscribe before the emptiness check below, not after - same ordering `next_job` uses, and
+    // for the same reason: a job enqueued in the gap between the check and the subscribe call
+    // would otherwise be missed, and long_poll_fetch_job would wait out the full timeout instead
+    // of waking as soon as it arrives.
+    let receiver = wait_secs.map(|_| data.job_notifier.subscribe(&queue_name));
+
+    match data.storage.fetch_queued_job(&queue_name, job_id).await {
+        Ok(Some(job)) => match project_fields(&job, &query, JOB_FIELDS) {
+            Ok(projected) => HttpResponse::Ok().json(projected),
+            Err(err) => err.error_response(),
+        },
+        Ok(None) => match (wait_secs, receiver) {
+            (Some(wait_secs), Some(receiver)) => {
+                long_poll_fetch_job(&data, &queue_name, job_id, &query, wait_secs, receiver).await
             }
-            _ => HttpResponse::NoContent().into(),
+            _ => match &data.config.server.next_job_delay {
+                Some(delay) if !delay.is_zero() => {
+                    tokio::time::sleep(delay.0).await;
+                    HttpResponse::NoContent().into()
+                }
+                _ => HttpResponse::NoContent().into(),
+            },
         },
         Err(err) => {
             error!("[queue:{}] failed to fetch job {}: {}", &queue_name, job_id, err);
@@ -214,21 +404,52 @@ pub async fn fetch_job(
     }
 }
 
+/// Holds a `fetch_job` request open for up to `wait_secs`, re-checking for `job_id` each time
+/// `receiver` reports *some* job was enqueued on `queue_name` - the same queue-level notification
+/// `long_poll_next_job` uses, just checking for a specific id arriving rather than the queue being
+/// non-empty. `receiver` must come from a `data.job_notifier.subscribe` call made *before*
+/// `fetch_job`'s initial check, so a job created in between isn't missed.
+async fn long_poll_fetch_job(
+    data: &ApplicationState,
+    queue_name: &str,
+    job_id: u64,
+    query: &QueueFields,
+    wait_secs: u64,
+    mut receiver: tokio::sync::broadcast::Receiver<()>,
+) -> HttpResponse {
+    let deadline = tokio::time::Instant::now() + std::time::Duration::from_secs(wait_secs);
+    loop {
+        let remaining = deadline.saturating_duration_since(tokio::time::Instant::now());
+        if remaining.is_zero() {
+            return HttpResponse::NoContent().into();
+        }
+        crate::notify::JobNotifier::wait(&mut receiver, remaining).await;
+        match data.storage.fetch_queued_job(queue_name, job_id).await {
+            Ok(Some(job)) => {
+                return match project_fields(&job, query, JOB_FIELDS) {
+                    Ok(projected) => HttpResponse::Ok().json(projected),
+                    Err(err) => err.error_response(),
+                }
+            }
+            Ok(None) => continue,
+            Err(err) => {
+                error!("[queue:{}] failed to fetch job {}: {}", queue_name, job_id, err);
+                return err.error_response();
+            }
+        }
+    }
+}
 
 pub async fn reattempt_job(
     path: web::Path<(String, i64)>,
     data: web::Data<ApplicationState>,
 ) -> impl Responder {
     let (queue_name, timestamp) = path.into_inner();
-    let mut conn = match data.redis_conn_pool.get().await {
-        Ok(conn) => conn,
-        Err(err) => return OcyError::from(err).error_response(),
-    }; 
 
     debug!("attempting to reattempt {:?} on {}", timestamp, &queue_name);
 
     match file::get_job(&queue_name, timestamp) {
-        Ok(mut job_req) => {
+        Ok((mut job_req, attempt)) => {
             debug!("attempting to reattempt {:?} on {}", job_req, timestamp);
             //this will not work in the input value is not an object
             if let Some(serde_json::Value::Object(input)) = &mut job_req.input {
@@ -236,14 +457,29 @@ pub async fn reattempt_job(
                     ("attempted_on".to_owned(), timestamp.into()),
                 ]);
             }
-            match RedisManager::create_job(&mut conn, &queue_name, &job_req).await {
-                Ok(job_id) => {
+            match data.storage.create_job(&queue_name, &job_req).await {
+                Ok(job::CreateOutcome::Created(job_id)) => {
                     debug!("deleting job attempt {:?} on {}", job_req, timestamp);
                     let _del = file::delete_job(&queue_name, timestamp);
+                    // This is a manual trigger of an already-scheduled retry, not a fresh
+                    // submission - seed the new job's attempt count from what was persisted here
+                    // so a subsequent failure still counts against the original retry budget.
+                    if let Err(err) = data.storage.set_job_attempt(job_id, attempt).await {
+                        error!("[queue:{}] failed to seed attempt count for reattempted job {}: {}", &queue_name, job_id, err);
+                    }
+                    data.job_notifier.notify(&queue_name);
+                    notify_job_created(&data, &queue_name, job_id).await;
                     HttpResponse::Created()
                         .append_header(("Location", format!("/job/{}", job_id)))
                         .json(job_id)
                 },
+                Ok(job::CreateOutcome::Existing(job_id)) => {
+                    debug!("deleting job attempt {:?} on {}", job_req, timestamp);
+                    let _del = file::delete_job(&queue_name, timestamp);
+                    HttpResponse::Ok()
+                        .append_header(("Location", format!("/job/{}", job_id)))
+                        .json(job_id)
+                },
                 Err(err) => {
                     error!("[queue:{}] failed to reattempt creating new job: {}", &queue_name, err);
                     err.error_response()
@@ -257,3 +493,63 @@ pub async fn reattempt_job(
     }
 }
 
+/// Handles `GET /queue/{queue_name}/dead` requests, listing jobs that exhausted their retries and
+/// were moved to the dead-letter store by the failure path in `handlers::job::fail`.
+///
+/// # Returns
+///
+/// * 200 - JSON array of `{timestamp, job, attempt}` dead-letter entries, oldest first.
+pub async fn dead_job_ids(path: web::Path<String>) -> impl Responder {
+    let queue_name = path.into_inner();
+
+    match file::list_dead_letters(&queue_name) {
+        Ok(entries) => {
+            let entries: Vec<_> = entries
+                .into_iter()
+                .map(|(timestamp, job_req, attempt)| {
+                    serde_json::json!({"timestamp": timestamp, "job": job_req, "attempt": attempt})
+                })
+                .collect();
+            HttpResponse::Ok().json(entries)
+        }
+        Err(err) => {
+            error!("[queue:{}] failed to list dead-letter entries: {}", &queue_name, err);
+            OcyError::Internal(err.to_string()).error_response()
+        }
+    }
+}
+
+/// Handles `POST /queue/{queue_name}/dead/{timestamp}/requeue` requests, re-submitting a
+/// dead-lettered attempt as a fresh job - the same `file::get_job` + re-create logic
+/// `reattempt_job` uses for its manual reattempts, read from the dead-letter store instead. Unlike
+/// `reattempt_job`, the attempt count isn't carried over: a dead letter was exhausted precisely
+/// because it hit `settings.retries`, so requeuing it here is treated as a deliberate decision to
+/// give it a fresh full retry budget rather than dead-lettering it again on its very next failure.
+pub async fn dead_requeue(
+    path: web::Path<(String, i64)>,
+    data: web::Data<ApplicationState>,
+) -> impl Responder {
+    let (queue_name, timestamp) = path.into_inner();
+
+    match file::get_dead_letter(&queue_name, timestamp) {
+        Ok((job_req, _attempt)) => match data.storage.create_job(&queue_name, &job_req).await {
+            Ok(job::CreateOutcome::Created(job_id)) | Ok(job::CreateOutcome::Existing(job_id)) => {
+                let _del = file::delete_dead_letter(&queue_name, timestamp);
+                data.job_notifier.notify(&queue_name);
+                notify_job_created(&data, &queue_name, job_id).await;
+                HttpResponse::Created()
+                    .append_header(("Location", format!("/job/{}", job_id)))
+                    .json(job_id)
+            }
+            Err(err) => {
+                error!("[queue:{}] failed to requeue dead-letter {}: {}", &queue_name, timestamp, err);
+                err.error_response()
+            }
+        },
+        Err(err) => {
+            error!("[queue:{}] failed to read dead-letter {}: {}", &queue_name, timestamp, err);
+            err.error_response()
+        }
+    }
+}
+
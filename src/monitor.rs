@@ -0,0 +1,63 @@
+//! Background task that reclaims jobs abandoned by dead workers.
+//!
+//! When `next_job`/`fetch_job` hands a job to a worker it's expected to call
+//! `POST /job/{id}/heartbeat` periodically while the job is `running`. This task wakes up on a
+//! fixed interval and asks storage to reclaim any running job whose heartbeat has gone stale past
+//! its queue's configured `heartbeat_timeout` - back to `queued` if it still has retries left, or
+//! to `failed` once they're exhausted. Spawned at startup alongside the HTTP server, in
+//! `jimmy-server.rs`.
+//!
+//! Every reclaimed job is reported via `JobEvent::TimedOut` - the same `Notifier` the explicit
+//! `/job/{id}/complete`/`/fail` endpoints use - and, once a job's retries are exhausted,
+//! `retry::on_job_failed` dead-letters it exactly as the explicit-fail path does. A job that's
+//! still requeued doesn't also go through `on_job_failed`: storage already re-enqueued it directly,
+//! so scheduling a second, file-backed retry on top would double up the attempt.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use log::{error, info};
+
+use crate::models::ApplicationState;
+use crate::notifier::JobEvent;
+use crate::retry;
+
+/// Runs the reclamation loop on `interval` until the process exits.
+pub async fn run(data: Arc<ApplicationState>, interval: Duration) {
+    let mut ticker = tokio::time::interval(interval);
+    loop {
+        ticker.tick().await;
+        match data.storage.reclaim_stale_jobs().await {
+            Ok(reclaimed) if reclaimed.is_empty() => {}
+            Ok(reclaimed) => {
+                info!("reclaimed {} job(s) from workers with a stale heartbeat", reclaimed.len());
+                for entry in reclaimed {
+                    data.notifier.notify(JobEvent::TimedOut, &entry.queue_name, &entry.job);
+
+                    if entry.requeued {
+                        data.job_notifier.notify(&entry.queue_name);
+                        continue;
+                    }
+
+                    let settings = match data.storage.queue_settings(&entry.queue_name).await {
+                        Ok(settings) => settings,
+                        Err(err) => {
+                            error!(
+                                "[queue:{}] failed to load settings to dead-letter reclaimed job {}: {}",
+                                entry.queue_name, entry.job.id, err
+                            );
+                            continue;
+                        }
+                    };
+                    if let Err(err) = retry::on_job_failed(&entry.queue_name, &settings, &entry.job) {
+                        error!(
+                            "[queue:{}] failed to dead-letter reclaimed job {}: {}",
+                            entry.queue_name, entry.job.id, err
+                        );
+                    }
+                }
+            }
+            Err(err) => error!("job reclamation pass failed: {}", err),
+        }
+    }
+}
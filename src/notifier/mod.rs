@@ -0,0 +1,47 @@
+//! Configurable notifier that fires HTTP callbacks when a job changes state.
+//!
+//! Emission is hooked in at the points where job state changes today: after a successful
+//! `create_job` and in the `reattempt_job` flow (both in `handlers::queue`), and at the
+//! completion/failure endpoints in `handlers::job`. Downstream systems can react to job outcomes
+//! this way instead of polling `GET /job/{id}`.
+
+pub mod webhook;
+
+use crate::models::job;
+
+/// Job lifecycle events a [`Notifier`] can be asked to report on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JobEvent {
+    Created,
+    Started,
+    Completed,
+    Failed,
+    TimedOut,
+}
+
+impl JobEvent {
+    /// The value placed in the `status` field of the JSON payload delivered for this event.
+    fn status(self) -> &'static str {
+        match self {
+            JobEvent::Created => "created",
+            JobEvent::Started => "started",
+            JobEvent::Completed => "completed",
+            JobEvent::Failed => "failed",
+            JobEvent::TimedOut => "timed_out",
+        }
+    }
+}
+
+/// Reports a job state transition. Implementations must not block the request handler that
+/// triggers `notify` - [`webhook::WebhookNotifier`] hands delivery off to a background task over
+/// a bounded channel so a slow endpoint can't stall request handling.
+pub trait Notifier: Send + Sync {
+    fn notify(&self, event: JobEvent, queue_name: &str, job: &job::Payload);
+}
+
+/// A [`Notifier`] that does nothing, used when no webhook targets are configured.
+pub struct NoopNotifier;
+
+impl Notifier for NoopNotifier {
+    fn notify(&self, _event: JobEvent, _queue_name: &str, _job: &job::Payload) {}
+}
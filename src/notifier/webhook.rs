@@ -0,0 +1,143 @@
+//! Webhook [`Notifier`] implementation: POSTs a JSON payload to per-queue or global target URLs
+//! configured in the server config.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use log::{error, warn};
+use serde_json::json;
+use tokio::sync::mpsc;
+
+use super::{JobEvent, Notifier};
+use crate::models::job;
+
+/// Bound on the number of pending deliveries buffered in memory *per target URL*. Once a target's
+/// queue is full, `notify` drops the event for that target rather than blocking the caller or
+/// growing the queue unbounded - a downstream system that wants guaranteed delivery should poll
+/// `GET /job/{id}` as a backstop.
+const QUEUE_CAPACITY: usize = 256;
+
+/// Number of times a delivery is attempted before it's given up on.
+const MAX_ATTEMPTS: u32 = 5;
+
+/// Backoff before the first retry; doubles on each subsequent attempt.
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+
+/// Per-request timeout applied to every webhook POST, so a target that never responds can't pin
+/// its delivery task (and, transitively, every other delivery already queued behind it) forever.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+struct Delivery {
+    url: String,
+    body: serde_json::Value,
+}
+
+/// Webhook notifier. `target_urls` maps a queue name to the URL(s) to notify for jobs on that
+/// queue; the empty-string key ("") is a global fallback applied in addition to any per-queue
+/// targets.
+///
+/// Delivery runs one task per target URL, each with its own bounded channel and retry/backoff
+/// loop - a single endpoint that's down or slow only delays (and, if its own queue fills, drops)
+/// deliveries to that endpoint, and never stalls delivery to any other target.
+pub struct WebhookNotifier {
+    target_urls: HashMap<String, Vec<String>>,
+    client: reqwest::Client,
+    senders: Mutex<HashMap<String, mpsc::Sender<Delivery>>>,
+}
+
+impl WebhookNotifier {
+    /// Builds a notifier for `target_urls`. Delivery tasks are spawned lazily, one per target URL
+    /// the first time it's notified on.
+    pub fn new(target_urls: HashMap<String, Vec<String>>) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .expect("reqwest client config is static and always valid");
+        WebhookNotifier {
+            target_urls,
+            client,
+            senders: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn urls_for(&self, queue_name: &str) -> impl Iterator<Item = &str> {
+        self.target_urls
+            .get(queue_name)
+            .into_iter()
+            .chain(self.target_urls.get(""))
+            .flatten()
+            .map(String::as_str)
+    }
+
+    /// Returns the delivery channel for `url`, spawning its dedicated delivery task on first use.
+    fn sender_for(&self, url: &str) -> mpsc::Sender<Delivery> {
+        let mut senders = self.senders.lock().unwrap();
+        senders
+            .entry(url.to_owned())
+            .or_insert_with(|| {
+                let (sender, receiver) = mpsc::channel(QUEUE_CAPACITY);
+                tokio::spawn(deliver_loop(url.to_owned(), receiver, self.client.clone()));
+                sender
+            })
+            .clone()
+    }
+}
+
+impl Notifier for WebhookNotifier {
+    fn notify(&self, event: JobEvent, queue_name: &str, job: &job::Payload) {
+        let body = json!({
+            "job_id": job.id,
+            "queue": queue_name,
+            "status": event.status(),
+            "ended_at": job.ended_at,
+            "output": job.output,
+        });
+
+        for url in self.urls_for(queue_name) {
+            let delivery = Delivery {
+                url: url.to_owned(),
+                body: body.clone(),
+            };
+            if self.sender_for(url).try_send(delivery).is_err() {
+                warn!(
+                    "[job:{}] webhook delivery queue for {} full or closed, dropping {} notification",
+                    job.id, url, event.status()
+                );
+            }
+        }
+    }
+}
+
+/// Consumes deliveries queued for a single target URL and POSTs them, retrying with exponential
+/// backoff. One task per target means a slow or unreachable endpoint only delays its own
+/// deliveries, never another target's.
+async fn deliver_loop(url: String, mut receiver: mpsc::Receiver<Delivery>, client: reqwest::Client) {
+    while let Some(delivery) = receiver.recv().await {
+        let mut backoff = INITIAL_BACKOFF;
+        for attempt in 1..=MAX_ATTEMPTS {
+            match client.post(&delivery.url).json(&delivery.body).send().await {
+                Ok(resp) if resp.status().is_success() => break,
+                Ok(resp) => {
+                    warn!(
+                        "webhook POST to {} returned {} (attempt {}/{})",
+                        url, resp.status(), attempt, MAX_ATTEMPTS
+                    );
+                }
+                Err(err) => {
+                    warn!(
+                        "webhook POST to {} failed: {} (attempt {}/{})",
+                        url, err, attempt, MAX_ATTEMPTS
+                    );
+                }
+            }
+
+            if attempt == MAX_ATTEMPTS {
+                error!("giving up on webhook delivery to {} after {} attempts", url, MAX_ATTEMPTS);
+                break;
+            }
+            tokio::time::sleep(backoff).await;
+            backoff *= 2;
+        }
+    }
+}
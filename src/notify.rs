@@ -0,0 +1,54 @@
+//! Per-queue notification channels used to wake long-polling `next_job`/`fetch_job` callers as
+//! soon as a job is enqueued, instead of making them wait out a fixed polling delay.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use tokio::sync::broadcast;
+
+/// Number of pending notifications a queue's channel can buffer before older ones are dropped.
+/// Receivers only care that *a* job arrived, not how many, so a small capacity is enough.
+const CHANNEL_CAPACITY: usize = 16;
+
+/// Registry of per-queue broadcast channels, shared across handlers via `ApplicationState`.
+#[derive(Default)]
+pub struct JobNotifier {
+    channels: Mutex<HashMap<String, broadcast::Sender<()>>>,
+}
+
+impl JobNotifier {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Wakes any callers currently waiting on `queue_name`. Safe to call when nobody is waiting.
+    pub fn notify(&self, queue_name: &str) {
+        let channels = self.channels.lock().unwrap();
+        if let Some(sender) = channels.get(queue_name) {
+            let _ = sender.send(());
+        }
+    }
+
+    /// Returns a receiver woken the next time `notify` is called for `queue_name`, creating the
+    /// channel on first use.
+    ///
+    /// Callers that are about to check whether a queue is empty and, if so, wait on it should
+    /// subscribe *before* that check - subscribing afterwards leaves a gap in which a `notify`
+    /// call is missed entirely, so the caller ends up waiting out the full timeout instead of
+    /// waking as soon as the job arrives.
+    pub fn subscribe(&self, queue_name: &str) -> broadcast::Receiver<()> {
+        let mut channels = self.channels.lock().unwrap();
+        channels
+            .entry(queue_name.to_owned())
+            .or_insert_with(|| broadcast::channel(CHANNEL_CAPACITY).0)
+            .subscribe()
+    }
+
+    /// Waits for either a notification on `receiver` or `timeout` to elapse, whichever comes
+    /// first. `receiver` should come from a `subscribe` call made before the caller's own
+    /// emptiness check, to avoid missing a notification sent in between.
+    pub async fn wait(receiver: &mut broadcast::Receiver<()>, timeout: Duration) {
+        let _ = tokio::time::timeout(timeout, receiver.recv()).await;
+    }
+}
@@ -0,0 +1,151 @@
+//! Retry / dead-letter subsystem built on top of the existing file-backed job attempt store.
+//!
+//! `handlers::job::fail` hooks in here when a job fails: if `queue::Settings::retries` allows
+//! another attempt, the job is written back to the file store under a computed next-attempt
+//! timestamp; once exhausted, it's moved to the dead-letter store instead. `run_scheduler`
+//! periodically re-enqueues any attempt whose timestamp has passed, via the same
+//! `Storage::create_job` path `handlers::queue::reattempt_job` uses for manual reattempts.
+
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use log::error;
+use log::debug;
+
+use crate::application::file;
+use crate::models::{job, queue, ApplicationState, OcyError};
+use crate::notifier::JobEvent;
+
+fn unix_now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+/// Delay before the next attempt, given a job that has already failed `attempt` times. Repeats
+/// the last entry of `retry_delays` once `attempt` runs past the configured schedule.
+fn backoff_delay(settings: &queue::Settings, attempt: u64) -> Duration {
+    backoff_for(&settings.retry_delays, attempt)
+}
+
+/// Pure delay-schedule lookup behind `backoff_delay`, split out so it's testable without needing
+/// a `queue::Settings` value.
+fn backoff_for(retry_delays: &[Duration], attempt: u64) -> Duration {
+    retry_delays
+        .get(attempt as usize)
+        .or_else(|| retry_delays.last())
+        .copied()
+        .unwrap_or(Duration::ZERO)
+}
+
+/// Called from the job failure path after a job has been marked `failed` in storage. If the job
+/// still has retries left per `settings.retries`, re-persists it to the file store under a
+/// computed next-attempt timestamp for `run_scheduler` to pick up; otherwise moves it to the
+/// dead-letter store for `handlers::queue::dead_job_ids` / `dead_requeue`.
+pub fn on_job_failed(
+    queue_name: &str,
+    settings: &queue::Settings,
+    job: &job::Payload,
+) -> Result<(), OcyError> {
+    let job_req = job.to_create_request();
+
+    if job.attempt >= settings.retries {
+        file::write_dead_letter(queue_name, &job_req, job.attempt)
+            .map_err(|err| OcyError::Internal(err.to_string()))?;
+        debug!(
+            "[queue:{}] job {} exhausted its retries, moved to dead-letter store",
+            queue_name, job.id
+        );
+        return Ok(());
+    }
+
+    let next_attempt_at = unix_now() + backoff_delay(settings, job.attempt).as_secs() as i64;
+    // `job.attempt` itself doesn't survive `run_due_attempts` re-submitting this through
+    // `Storage::create_job` under a new job id - record the attempt number it'll be on here, and
+    // `run_due_attempts` seeds it back onto the new job via `Storage::set_job_attempt`.
+    file::write_job_at(queue_name, &job_req, next_attempt_at, job.attempt + 1)
+        .map_err(|err| OcyError::Internal(err.to_string()))?;
+    debug!(
+        "[queue:{}] job {} scheduled for retry at {}",
+        queue_name, job.id, next_attempt_at
+    );
+    Ok(())
+}
+
+/// Periodically scans every queue's file store for attempts whose next-attempt timestamp has
+/// passed and re-enqueues them via `Storage::create_job`, exactly as
+/// `handlers::queue::reattempt_job` does for a single manually-triggered attempt.
+pub async fn run_scheduler(data: Arc<ApplicationState>, interval: Duration) {
+    let mut ticker = tokio::time::interval(interval);
+    loop {
+        ticker.tick().await;
+        if let Err(err) = run_due_attempts(&data).await {
+            error!("retry scheduler pass failed: {}", err);
+        }
+    }
+}
+
+async fn run_due_attempts(data: &ApplicationState) -> Result<(), OcyError> {
+    let now = unix_now();
+    for queue_name in data.storage.queue_names().await? {
+        let due = file::due_attempts(&queue_name, now).map_err(|err| OcyError::Internal(err.to_string()))?;
+        for timestamp in due {
+            let (job_req, attempt) = match file::get_job(&queue_name, timestamp) {
+                Ok(entry) => entry,
+                Err(err) => {
+                    error!(
+                        "[queue:{}] failed to read due attempt {}: {}",
+                        queue_name, timestamp, err
+                    );
+                    continue;
+                }
+            };
+
+            match data.storage.create_job(&queue_name, &job_req).await {
+                Ok(job::CreateOutcome::Created(job_id)) | Ok(job::CreateOutcome::Existing(job_id)) => {
+                    let _del = file::delete_job(&queue_name, timestamp);
+                    // Seed the re-created job's attempt count from what was persisted here, since
+                    // it got a brand-new id and would otherwise start back at 0 - without this the
+                    // dead-letter threshold in `on_job_failed` could never trip for a job that's
+                    // ever been retried.
+                    if let Err(err) = data.storage.set_job_attempt(job_id, attempt).await {
+                        error!(
+                            "[queue:{}] failed to seed attempt count for re-enqueued job {}: {}",
+                            queue_name, job_id, err
+                        );
+                    }
+                    data.job_notifier.notify(&queue_name);
+                    if let Ok(Some(job)) = data.storage.fetch_queued_job(&queue_name, job_id).await {
+                        data.notifier.notify(JobEvent::Created, &queue_name, &job);
+                    }
+                }
+                Err(err) => error!(
+                    "[queue:{}] failed to re-enqueue due attempt {}: {}",
+                    queue_name, timestamp, err
+                ),
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn follows_the_configured_schedule_then_repeats_the_last_delay() {
+        let delays = vec![Duration::from_secs(1), Duration::from_secs(4), Duration::from_secs(16)];
+        assert_eq!(backoff_for(&delays, 0), Duration::from_secs(1));
+        assert_eq!(backoff_for(&delays, 1), Duration::from_secs(4));
+        assert_eq!(backoff_for(&delays, 2), Duration::from_secs(16));
+        assert_eq!(backoff_for(&delays, 10), Duration::from_secs(16));
+    }
+
+    #[test]
+    fn empty_schedule_means_no_delay() {
+        assert_eq!(backoff_for(&[], 0), Duration::ZERO);
+        assert_eq!(backoff_for(&[], 5), Duration::ZERO);
+    }
+}
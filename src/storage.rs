@@ -0,0 +1,811 @@
+//! Backend-agnostic storage abstraction used by the HTTP handlers.
+//!
+//! Handlers previously called concrete `RedisManager` functions directly against a
+//! `redis_conn_pool`. The [`Storage`] trait pulls those operations out behind an interface so
+//! `ApplicationState` can hold any implementation - Redis in production, an in-memory store for
+//! tests and single-node deployments where a separate Redis instance isn't worth running.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use async_trait::async_trait;
+use log::error;
+use redis::AsyncCommands;
+
+use crate::application::RedisManager;
+use crate::models::{job, queue, OcyError};
+
+fn unix_now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+/// Operations a job queue backend must provide. Handlers hold a single `Box<dyn Storage>` shared
+/// across requests via `ApplicationState`, so implementations must be `Send + Sync`; they are not
+/// cloned.
+#[async_trait]
+pub trait Storage: Send + Sync {
+    /// Returns the names of all existing queues.
+    async fn queue_names(&self) -> Result<Vec<String>, OcyError>;
+
+    /// Creates a queue with the given settings, or updates its settings if it already exists.
+    /// Returns `true` if a new queue was created, `false` if an existing queue was updated.
+    async fn create_or_update_queue(
+        &self,
+        queue_name: &str,
+        settings: &queue::Settings,
+    ) -> Result<bool, OcyError>;
+
+    /// Deletes a queue and all of its jobs. Returns `true` if the queue existed.
+    async fn delete_queue(&self, queue_name: &str) -> Result<bool, OcyError>;
+
+    /// Fetches the settings a queue was created/updated with.
+    async fn queue_settings(&self, queue_name: &str) -> Result<queue::Settings, OcyError>;
+
+    /// Returns the number of jobs currently queued.
+    async fn queue_size(&self, queue_name: &str) -> Result<u64, OcyError>;
+
+    /// Returns the ids of all jobs currently queued, in FIFO order.
+    async fn queue_job_ids(&self, queue_name: &str) -> Result<Vec<u64>, OcyError>;
+
+    /// Creates a new job on a queue and returns its id. If `job_req.unique_key` names a key that
+    /// is already live for an in-flight job on this queue, no new job is created and the id of
+    /// that existing job is returned instead - callers distinguish the two cases via
+    /// `job::CreateOutcome`.
+    async fn create_job(
+        &self,
+        queue_name: &str,
+        job_req: &job::CreateRequest,
+    ) -> Result<job::CreateOutcome, OcyError>;
+
+    /// Claims and returns the next queued job on a queue, if one is available.
+    async fn next_queued_job(&self, queue_name: &str) -> Result<Option<job::Payload>, OcyError>;
+
+    /// Fetches a specific job by id without regard to its queue state.
+    async fn fetch_queued_job(
+        &self,
+        queue_name: &str,
+        job_id: u64,
+    ) -> Result<Option<job::Payload>, OcyError>;
+
+    /// Pushes a pre-built job payload directly onto a queue, for callers that already have a
+    /// fully-formed job rather than a fresh `CreateRequest`. The retry, reclaim and requeue flows
+    /// re-submit through `create_job`, not through this.
+    async fn push(&self, queue_name: &str, job: job::Payload) -> Result<u64, OcyError>;
+
+    /// Pops the next queued job for `runner_id`, equivalent to `next_queued_job` but named to
+    /// mirror the producer-side `push`.
+    async fn pop(&self, queue_name: &str, runner_id: &str) -> Result<Option<job::Payload>, OcyError>;
+
+    /// Refreshes the liveness timestamp for a job owned by `runner_id`.
+    async fn heartbeat(&self, job_id: u64, runner_id: &str) -> Result<(), OcyError>;
+
+    /// Marks a job owned by `runner_id` as complete, storing its output. Returns the job's queue
+    /// name and final payload so callers (e.g. the `Notifier`) don't need a second read.
+    async fn complete(
+        &self,
+        job_id: u64,
+        runner_id: &str,
+        output: Option<serde_json::Value>,
+    ) -> Result<(String, job::Payload), OcyError>;
+
+    /// Marks a job owned by `runner_id` as failed, storing an error message. Returns the job's
+    /// queue name and final payload, same as `complete`.
+    async fn fail(
+        &self,
+        job_id: u64,
+        runner_id: &str,
+        error_msg: Option<String>,
+    ) -> Result<(String, job::Payload), OcyError>;
+
+    /// Scans running jobs across all queues and reclaims any whose heartbeat is older than its
+    /// queue's configured `heartbeat_timeout`: back to `queued` if it has retries left, or to
+    /// `failed` once they're exhausted. Returns one entry per job reclaimed so the caller (the
+    /// background monitor task in [`crate::monitor`]) can notify and dead-letter exhausted jobs
+    /// the same way the explicit `/job/{id}/fail` path does.
+    async fn reclaim_stale_jobs(&self) -> Result<Vec<ReclaimedJob>, OcyError>;
+
+    /// Seeds the attempt count tracked for `job_id`. Used by `retry::run_due_attempts` right after
+    /// re-submitting a retried job through `create_job`, since that allocates a new id with no
+    /// attempt history of its own - without this, `fail`/`reclaim_stale_jobs` would see every
+    /// retried job as a fresh attempt 0 and the dead-letter threshold would never trip.
+    async fn set_job_attempt(&self, job_id: u64, attempt: u64) -> Result<(), OcyError>;
+}
+
+/// One job reclaimed by a [`Storage::reclaim_stale_jobs`] pass.
+pub struct ReclaimedJob {
+    pub queue_name: String,
+    pub job: job::Payload,
+    /// `true` if the job still had retries left and was requeued; `false` if its retries were
+    /// exhausted and it was marked `failed` instead.
+    pub requeued: bool,
+}
+
+/// [`Storage`] implementation backed by the existing Redis data model.
+pub struct RedisStorage {
+    redis_conn_pool: deadpool_redis::Pool,
+}
+
+impl RedisStorage {
+    /// Wraps an existing Redis connection pool as a [`Storage`] backend.
+    pub fn new(redis_conn_pool: deadpool_redis::Pool) -> Self {
+        RedisStorage { redis_conn_pool }
+    }
+
+    async fn conn(&self) -> Result<deadpool_redis::Connection, OcyError> {
+        self.redis_conn_pool
+            .get()
+            .await
+            .map_err(OcyError::RedisConnection)
+    }
+
+    // The job/queue data model itself (job hashes, pending lists, queue settings) lives in
+    // `RedisManager`. The keys below back capabilities layered on top of it by this storage - job
+    // liveness tracking (`heartbeat`/`complete`/`fail`/`reclaim_stale_jobs`) and unique-key dedup -
+    // that have no equivalent in the pre-existing data model.
+
+    /// Maps a job id to the name of the queue it was created on, so `heartbeat`/`complete`/`fail`
+    /// can resolve a queue from a bare job id.
+    fn job_queue_key(job_id: u64) -> String {
+        format!("storage:job:{}:queue", job_id)
+    }
+
+    /// The `CreateRequest` a job was created with, kept around so it can be rebuilt and
+    /// re-submitted by `reclaim_stale_jobs`.
+    fn job_request_key(job_id: u64) -> String {
+        format!("storage:job:{}:request", job_id)
+    }
+
+    /// Liveness/terminal-state metadata for a job: `runner_id`, `status`, `heartbeat_at`,
+    /// `attempt`, and (if it was created with a `unique_key`) `unique_key_entry` pointing back at
+    /// the dedup key below so it can be cleared once the job finishes.
+    fn job_state_key(job_id: u64) -> String {
+        format!("storage:job:{}:state", job_id)
+    }
+
+    /// Set of job ids on `queue_name` that have sent a heartbeat and not yet completed or failed,
+    /// scanned by `reclaim_stale_jobs` for stale heartbeats. A job is added by its first
+    /// `heartbeat`, not when `next_queued_job` hands it out, so a claimed job that never sends a
+    /// heartbeat is not in this set and is never reclaimed.
+    fn running_set_key(queue_name: &str) -> String {
+        format!("storage:queue:{}:running", queue_name)
+    }
+
+    /// Maps a queue's live `unique_key` to the id of the job it was submitted with.
+    fn unique_key_entry(queue_name: &str, unique_key: &str) -> String {
+        format!("storage:queue:{}:unique:{}", queue_name, unique_key)
+    }
+
+    /// Clears the unique-key dedup entry (if any) registered for `job_id` once it reaches a
+    /// terminal state, so the same key can be reused by a future submission.
+    async fn clear_unique_key(
+        conn: &mut deadpool_redis::Connection,
+        job_id: u64,
+    ) -> Result<(), OcyError> {
+        let entry: Option<String> = conn
+            .hget(Self::job_state_key(job_id), "unique_key_entry")
+            .await
+            .map_err(OcyError::from)?;
+        if let Some(entry) = entry {
+            let _: () = conn.del(entry).await.map_err(OcyError::from)?;
+        }
+        Ok(())
+    }
+
+    /// Looks up the queue a job was created on and the `CreateRequest` it was created with, as
+    /// recorded by `create_job`/`push`.
+    async fn load_job_request(
+        &self,
+        job_id: u64,
+    ) -> Result<(String, job::CreateRequest), OcyError> {
+        let mut conn = self.conn().await?;
+        let queue_name: Option<String> = conn
+            .get(Self::job_queue_key(job_id))
+            .await
+            .map_err(OcyError::from)?;
+        let queue_name = queue_name.ok_or(OcyError::NoSuchJob(job_id))?;
+
+        let request_json: Option<String> = conn
+            .get(Self::job_request_key(job_id))
+            .await
+            .map_err(OcyError::from)?;
+        let request_json = request_json.ok_or(OcyError::NoSuchJob(job_id))?;
+        let job_req = serde_json::from_str(&request_json)
+            .map_err(|err| OcyError::Internal(err.to_string()))?;
+        Ok((queue_name, job_req))
+    }
+}
+
+#[async_trait]
+impl Storage for RedisStorage {
+    async fn queue_names(&self) -> Result<Vec<String>, OcyError> {
+        RedisManager::queue_names(&mut self.conn().await?).await
+    }
+
+    async fn create_or_update_queue(
+        &self,
+        queue_name: &str,
+        settings: &queue::Settings,
+    ) -> Result<bool, OcyError> {
+        RedisManager::create_or_update_queue(&mut self.conn().await?, queue_name, settings).await
+    }
+
+    async fn delete_queue(&self, queue_name: &str) -> Result<bool, OcyError> {
+        RedisManager::delete_queue(&mut self.conn().await?, queue_name).await
+    }
+
+    async fn queue_settings(&self, queue_name: &str) -> Result<queue::Settings, OcyError> {
+        RedisManager::queue_settings(&mut self.conn().await?, queue_name).await
+    }
+
+    async fn queue_size(&self, queue_name: &str) -> Result<u64, OcyError> {
+        RedisManager::queue_size(&mut self.conn().await?, queue_name).await
+    }
+
+    async fn queue_job_ids(&self, queue_name: &str) -> Result<Vec<u64>, OcyError> {
+        RedisManager::queue_job_ids(&mut self.conn().await?, queue_name).await
+    }
+
+    async fn create_job(
+        &self,
+        queue_name: &str,
+        job_req: &job::CreateRequest,
+    ) -> Result<job::CreateOutcome, OcyError> {
+        let mut conn = self.conn().await?;
+
+        // Reserve the unique-key entry with a placeholder via SET NX *before* creating the job,
+        // collapsing the previous GET-then-SET window down to a single round trip: two concurrent
+        // submissions for the same `unique_key` can no longer both observe "no existing entry"
+        // and both go on to create a job.
+        let mut reserved_entry_key = None;
+        if let Some(unique_key) = &job_req.unique_key {
+            let entry_key = Self::unique_key_entry(queue_name, unique_key);
+            let reserved: bool = conn.set_nx(&entry_key, 0u64).await.map_err(OcyError::from)?;
+            if !reserved {
+                let existing_id: u64 = conn.get(&entry_key).await.map_err(OcyError::from)?;
+                return Ok(job::CreateOutcome::Existing(existing_id));
+            }
+            reserved_entry_key = Some(entry_key);
+        }
+
+        let outcome = match RedisManager::create_job(&mut conn, queue_name, job_req).await {
+            Ok(outcome) => outcome,
+            Err(err) => {
+                if let Some(entry_key) = &reserved_entry_key {
+                    let _: () = conn.del(entry_key).await.map_err(OcyError::from)?;
+                }
+                return Err(err);
+            }
+        };
+
+        match outcome {
+            job::CreateOutcome::Created(job_id) => {
+                let request_json = serde_json::to_string(job_req)
+                    .map_err(|err| OcyError::Internal(err.to_string()))?;
+                let _: () = conn
+                    .set(Self::job_queue_key(job_id), queue_name)
+                    .await
+                    .map_err(OcyError::from)?;
+                let _: () = conn
+                    .set(Self::job_request_key(job_id), request_json)
+                    .await
+                    .map_err(OcyError::from)?;
+
+                // Replace the placeholder reserved above with the real job id now that it exists.
+                if let Some(entry_key) = &reserved_entry_key {
+                    let _: () = conn.set(entry_key, job_id).await.map_err(OcyError::from)?;
+                    let _: () = conn
+                        .hset(Self::job_state_key(job_id), "unique_key_entry", entry_key)
+                        .await
+                        .map_err(OcyError::from)?;
+                }
+            }
+            job::CreateOutcome::Existing(_) => {
+                // `RedisManager::create_job` reported an existing job through its own path -
+                // release the placeholder reserved above so it doesn't leak.
+                if let Some(entry_key) = &reserved_entry_key {
+                    let _: () = conn.del(entry_key).await.map_err(OcyError::from)?;
+                }
+            }
+        }
+
+        Ok(outcome)
+    }
+
+    async fn next_queued_job(&self, queue_name: &str) -> Result<Option<job::Payload>, OcyError> {
+        RedisManager::next_queued_job(&mut self.conn().await?, queue_name).await
+    }
+
+    async fn fetch_queued_job(
+        &self,
+        queue_name: &str,
+        job_id: u64,
+    ) -> Result<Option<job::Payload>, OcyError> {
+        RedisManager::fetch_queued_job(&mut self.conn().await?, queue_name, job_id).await
+    }
+
+    async fn push(&self, queue_name: &str, job: job::Payload) -> Result<u64, OcyError> {
+        // There's no primitive to re-insert a job under its original id, so re-pushing a payload
+        // goes through `create_job` exactly like a fresh submission, and is allocated a new id as
+        // a result.
+        match self.create_job(queue_name, &job.to_create_request()).await? {
+            job::CreateOutcome::Created(job_id) | job::CreateOutcome::Existing(job_id) => Ok(job_id),
+        }
+    }
+
+    async fn pop(&self, queue_name: &str, _runner_id: &str) -> Result<Option<job::Payload>, OcyError> {
+        self.next_queued_job(queue_name).await
+    }
+
+    async fn heartbeat(&self, job_id: u64, runner_id: &str) -> Result<(), OcyError> {
+        let mut conn = self.conn().await?;
+        let queue_name: Option<String> = conn
+            .get(Self::job_queue_key(job_id))
+            .await
+            .map_err(OcyError::from)?;
+        let queue_name = queue_name.ok_or(OcyError::NoSuchJob(job_id))?;
+
+        let state_key = Self::job_state_key(job_id);
+        let owner: Option<String> = conn.hget(&state_key, "runner_id").await.map_err(OcyError::from)?;
+        if matches!(&owner, Some(owner) if owner != runner_id) {
+            return Err(OcyError::NoSuchJob(job_id));
+        }
+
+        let _: () = conn
+            .hset_multiple(
+                &state_key,
+                &[
+                    ("runner_id", runner_id.to_owned()),
+                    ("status", "running".to_owned()),
+                    ("heartbeat_at", unix_now().to_string()),
+                ],
+            )
+            .await
+            .map_err(OcyError::from)?;
+        let _: () = conn
+            .sadd(Self::running_set_key(&queue_name), job_id)
+            .await
+            .map_err(OcyError::from)?;
+        Ok(())
+    }
+
+    async fn complete(
+        &self,
+        job_id: u64,
+        runner_id: &str,
+        output: Option<serde_json::Value>,
+    ) -> Result<(String, job::Payload), OcyError> {
+        let (queue_name, job_req) = self.load_job_request(job_id).await?;
+        let mut job = job::Payload::from_create_request(job_id, &queue_name, &job_req);
+        job.complete(runner_id, output.clone())?;
+
+        let mut conn = self.conn().await?;
+        let _: () = conn
+            .hset_multiple(
+                Self::job_state_key(job_id),
+                &[
+                    ("status", "completed".to_owned()),
+                    ("ended_at", unix_now().to_string()),
+                    (
+                        "output",
+                        output.map(|value| value.to_string()).unwrap_or_default(),
+                    ),
+                ],
+            )
+            .await
+            .map_err(OcyError::from)?;
+        let _: () = conn
+            .srem(Self::running_set_key(&queue_name), job_id)
+            .await
+            .map_err(OcyError::from)?;
+        Self::clear_unique_key(&mut conn, job_id).await?;
+
+        Ok((queue_name, job))
+    }
+
+    async fn fail(
+        &self,
+        job_id: u64,
+        runner_id: &str,
+        error_msg: Option<String>,
+    ) -> Result<(String, job::Payload), OcyError> {
+        let (queue_name, job_req) = self.load_job_request(job_id).await?;
+        let mut job = job::Payload::from_create_request(job_id, &queue_name, &job_req);
+
+        let mut conn = self.conn().await?;
+        let state_key = Self::job_state_key(job_id);
+        // `job_id` may be a job that `run_due_attempts` re-created under a new id after a
+        // previous failure - `set_job_attempt` seeds its attempt count into this hash, so read it
+        // back here rather than leaving `job.attempt` at the 0 `from_create_request` defaults to.
+        job.attempt = conn.hget(&state_key, "attempt").await.unwrap_or(0);
+
+        job.fail(runner_id, error_msg.clone())?;
+
+        let _: () = conn
+            .hset_multiple(
+                &state_key,
+                &[
+                    ("status", "failed".to_owned()),
+                    ("ended_at", unix_now().to_string()),
+                    ("error_msg", error_msg.unwrap_or_default()),
+                ],
+            )
+            .await
+            .map_err(OcyError::from)?;
+        let _: () = conn
+            .srem(Self::running_set_key(&queue_name), job_id)
+            .await
+            .map_err(OcyError::from)?;
+        Self::clear_unique_key(&mut conn, job_id).await?;
+
+        Ok((queue_name, job))
+    }
+
+    async fn reclaim_stale_jobs(&self) -> Result<Vec<ReclaimedJob>, OcyError> {
+        let mut conn = self.conn().await?;
+        let mut reclaimed = Vec::new();
+
+        for queue_name in RedisManager::queue_names(&mut conn).await? {
+            let settings = RedisManager::queue_settings(&mut conn, &queue_name).await?;
+            let Some(heartbeat_timeout) = settings.heartbeat_timeout else {
+                continue;
+            };
+
+            let running: Vec<u64> = conn
+                .smembers(Self::running_set_key(&queue_name))
+                .await
+                .map_err(OcyError::from)?;
+            let now = unix_now();
+
+            for job_id in running {
+                let state_key = Self::job_state_key(job_id);
+                let heartbeat_at: Option<i64> = conn
+                    .hget(&state_key, "heartbeat_at")
+                    .await
+                    .map_err(OcyError::from)?;
+                let Some(heartbeat_at) = heartbeat_at else {
+                    continue;
+                };
+                if now - heartbeat_at <= heartbeat_timeout.as_secs() as i64 {
+                    continue;
+                }
+
+                let attempt: u64 = conn.hget(&state_key, "attempt").await.unwrap_or(0);
+                let _: () = conn
+                    .srem(Self::running_set_key(&queue_name), job_id)
+                    .await
+                    .map_err(OcyError::from)?;
+
+                let Ok((_, job_req)) = self.load_job_request(job_id).await else {
+                    continue;
+                };
+                let mut job = job::Payload::from_create_request(job_id, &queue_name, &job_req);
+                job.attempt = attempt;
+                let requeued = attempt < settings.retries;
+
+                if requeued {
+                    // No primitive exists to reinsert a job under its original id, so a reclaimed
+                    // job is re-enqueued the same way a fresh submission would be - through
+                    // `self.create_job`, not the bare `RedisManager::create_job` - so the new id
+                    // gets the `job_queue_key`/`job_request_key`/unique-key bookkeeping that
+                    // `heartbeat`/`complete`/`fail`/a future reclaim depend on. Clear the old id's
+                    // unique-key entry first so `create_job`'s dedup check doesn't see the
+                    // about-to-be-abandoned job and return it as "existing" instead of creating a
+                    // new one.
+                    Self::clear_unique_key(&mut conn, job_id).await?;
+                    match self.create_job(&queue_name, &job_req).await {
+                        Ok(job::CreateOutcome::Created(new_id))
+                        | Ok(job::CreateOutcome::Existing(new_id)) => {
+                            job.id = new_id;
+                            if let Err(err) = self.set_job_attempt(new_id, attempt + 1).await {
+                                error!(
+                                    "[queue:{}] failed to seed attempt count for reclaimed job {} (new id {}): {}",
+                                    queue_name, job_id, new_id, err
+                                );
+                            }
+                        }
+                        Err(err) => {
+                            error!(
+                                "[queue:{}] failed to re-enqueue reclaimed job {}: {}",
+                                queue_name, job_id, err
+                            );
+                            continue;
+                        }
+                    }
+                } else {
+                    let _: () = conn
+                        .hset(&state_key, "status", "failed")
+                        .await
+                        .map_err(OcyError::from)?;
+                    Self::clear_unique_key(&mut conn, job_id).await?;
+                }
+
+                reclaimed.push(ReclaimedJob { queue_name: queue_name.clone(), job, requeued });
+            }
+        }
+
+        Ok(reclaimed)
+    }
+
+    async fn set_job_attempt(&self, job_id: u64, attempt: u64) -> Result<(), OcyError> {
+        let _: () = self
+            .conn()
+            .await?
+            .hset(Self::job_state_key(job_id), "attempt", attempt)
+            .await
+            .map_err(OcyError::from)?;
+        Ok(())
+    }
+}
+
+pub mod mem {
+    //! An in-memory [`Storage`] implementation for tests and single-node deployments that don't
+    //! want to run a separate Redis instance. Not persisted across restarts.
+
+    use std::collections::BTreeMap;
+    use std::sync::Mutex;
+
+    use async_trait::async_trait;
+
+    use super::Storage;
+    use crate::models::{job, queue, OcyError};
+
+    struct QueueState {
+        settings: queue::Settings,
+        pending: Vec<u64>,
+        jobs: BTreeMap<u64, job::Payload>,
+        /// Maps a live `unique_key` to the id of the job it was submitted with, mirroring the
+        /// Redis-backed dedup set. Cleared once that job reaches a terminal state.
+        unique_keys: BTreeMap<String, u64>,
+    }
+
+    /// In-memory, single-process [`Storage`] backend, for tests and small deployments that don't
+    /// need a separate Redis instance. `next_queued_job`/`pop` never block - callers that want to
+    /// wait for a job use the handler-level long-poll in `handlers::queue::next_job`, which is
+    /// backed by `ApplicationState`'s `JobNotifier` rather than anything in this module.
+    #[derive(Default)]
+    pub struct MemStorage {
+        queues: Mutex<BTreeMap<String, QueueState>>,
+        next_id: Mutex<u64>,
+    }
+
+    impl MemStorage {
+        pub fn new() -> Self {
+            Self::default()
+        }
+
+        fn alloc_id(&self) -> u64 {
+            let mut next_id = self.next_id.lock().unwrap();
+            *next_id += 1;
+            *next_id
+        }
+    }
+
+    #[async_trait]
+    impl Storage for MemStorage {
+        async fn queue_names(&self) -> Result<Vec<String>, OcyError> {
+            Ok(self.queues.lock().unwrap().keys().cloned().collect())
+        }
+
+        async fn create_or_update_queue(
+            &self,
+            queue_name: &str,
+            settings: &queue::Settings,
+        ) -> Result<bool, OcyError> {
+            let mut queues = self.queues.lock().unwrap();
+            let created = !queues.contains_key(queue_name);
+            queues
+                .entry(queue_name.to_owned())
+                .and_modify(|q| q.settings = settings.clone())
+                .or_insert_with(|| QueueState {
+                    settings: settings.clone(),
+                    pending: Vec::new(),
+                    jobs: BTreeMap::new(),
+                    unique_keys: BTreeMap::new(),
+                });
+            Ok(created)
+        }
+
+        async fn delete_queue(&self, queue_name: &str) -> Result<bool, OcyError> {
+            Ok(self.queues.lock().unwrap().remove(queue_name).is_some())
+        }
+
+        async fn queue_settings(&self, queue_name: &str) -> Result<queue::Settings, OcyError> {
+            self.queues
+                .lock()
+                .unwrap()
+                .get(queue_name)
+                .map(|q| q.settings.clone())
+                .ok_or_else(|| OcyError::NoSuchQueue(queue_name.to_owned()))
+        }
+
+        async fn queue_size(&self, queue_name: &str) -> Result<u64, OcyError> {
+            self.queues
+                .lock()
+                .unwrap()
+                .get(queue_name)
+                .map(|q| q.pending.len() as u64)
+                .ok_or_else(|| OcyError::NoSuchQueue(queue_name.to_owned()))
+        }
+
+        async fn queue_job_ids(&self, queue_name: &str) -> Result<Vec<u64>, OcyError> {
+            self.queues
+                .lock()
+                .unwrap()
+                .get(queue_name)
+                .map(|q| q.pending.clone())
+                .ok_or_else(|| OcyError::NoSuchQueue(queue_name.to_owned()))
+        }
+
+        async fn create_job(
+            &self,
+            queue_name: &str,
+            job_req: &job::CreateRequest,
+        ) -> Result<job::CreateOutcome, OcyError> {
+            if let Some(unique_key) = &job_req.unique_key {
+                let existing = self
+                    .queues
+                    .lock()
+                    .unwrap()
+                    .get(queue_name)
+                    .ok_or_else(|| OcyError::NoSuchQueue(queue_name.to_owned()))?
+                    .unique_keys
+                    .get(unique_key)
+                    .copied();
+                if let Some(job_id) = existing {
+                    return Ok(job::CreateOutcome::Existing(job_id));
+                }
+            }
+
+            let job_id = self.alloc_id();
+            let payload = job::Payload::from_create_request(job_id, queue_name, job_req);
+            self.push(queue_name, payload).await?;
+            if let Some(unique_key) = &job_req.unique_key {
+                let mut queues = self.queues.lock().unwrap();
+                if let Some(queue) = queues.get_mut(queue_name) {
+                    queue.unique_keys.insert(unique_key.clone(), job_id);
+                }
+            }
+            Ok(job::CreateOutcome::Created(job_id))
+        }
+
+        async fn next_queued_job(&self, queue_name: &str) -> Result<Option<job::Payload>, OcyError> {
+            let mut queues = self.queues.lock().unwrap();
+            let queue = queues
+                .get_mut(queue_name)
+                .ok_or_else(|| OcyError::NoSuchQueue(queue_name.to_owned()))?;
+            Ok(queue
+                .pending
+                .first()
+                .copied()
+                .map(|id| {
+                    queue.pending.remove(0);
+                    queue.jobs.get(&id).cloned()
+                })
+                .flatten())
+        }
+
+        async fn fetch_queued_job(
+            &self,
+            queue_name: &str,
+            job_id: u64,
+        ) -> Result<Option<job::Payload>, OcyError> {
+            self.queues
+                .lock()
+                .unwrap()
+                .get(queue_name)
+                .ok_or_else(|| OcyError::NoSuchQueue(queue_name.to_owned()))
+                .map(|q| q.jobs.get(&job_id).cloned())
+        }
+
+        async fn push(&self, queue_name: &str, job: job::Payload) -> Result<u64, OcyError> {
+            let job_id = job.id;
+            {
+                let mut queues = self.queues.lock().unwrap();
+                let queue = queues
+                    .get_mut(queue_name)
+                    .ok_or_else(|| OcyError::NoSuchQueue(queue_name.to_owned()))?;
+                queue.jobs.insert(job_id, job);
+                queue.pending.push(job_id);
+            }
+            Ok(job_id)
+        }
+
+        async fn pop(&self, queue_name: &str, _runner_id: &str) -> Result<Option<job::Payload>, OcyError> {
+            self.next_queued_job(queue_name).await
+        }
+
+        async fn heartbeat(&self, job_id: u64, runner_id: &str) -> Result<(), OcyError> {
+            let mut queues = self.queues.lock().unwrap();
+            for queue in queues.values_mut() {
+                if let Some(job) = queue.jobs.get_mut(&job_id) {
+                    job.touch_heartbeat(runner_id)?;
+                    return Ok(());
+                }
+            }
+            Err(OcyError::NoSuchJob(job_id))
+        }
+
+        async fn complete(
+            &self,
+            job_id: u64,
+            runner_id: &str,
+            output: Option<serde_json::Value>,
+        ) -> Result<(String, job::Payload), OcyError> {
+            let mut queues = self.queues.lock().unwrap();
+            for (queue_name, queue) in queues.iter_mut() {
+                if let Some(job) = queue.jobs.get_mut(&job_id) {
+                    job.complete(runner_id, output)?;
+                    // The job reached a terminal state - free its unique key, if any, so the
+                    // same logical work can be re-submitted later.
+                    queue.unique_keys.retain(|_, id| *id != job_id);
+                    return Ok((queue_name.clone(), job.clone()));
+                }
+            }
+            Err(OcyError::NoSuchJob(job_id))
+        }
+
+        async fn fail(
+            &self,
+            job_id: u64,
+            runner_id: &str,
+            error_msg: Option<String>,
+        ) -> Result<(String, job::Payload), OcyError> {
+            let mut queues = self.queues.lock().unwrap();
+            for (queue_name, queue) in queues.iter_mut() {
+                if let Some(job) = queue.jobs.get_mut(&job_id) {
+                    job.fail(runner_id, error_msg)?;
+                    queue.unique_keys.retain(|_, id| *id != job_id);
+                    return Ok((queue_name.clone(), job.clone()));
+                }
+            }
+            Err(OcyError::NoSuchJob(job_id))
+        }
+
+        async fn reclaim_stale_jobs(&self) -> Result<Vec<super::ReclaimedJob>, OcyError> {
+            let mut queues = self.queues.lock().unwrap();
+            let mut reclaimed = Vec::new();
+            for (queue_name, queue) in queues.iter_mut() {
+                let Some(heartbeat_timeout) = queue.settings.heartbeat_timeout else {
+                    continue;
+                };
+                let stale_ids: Vec<u64> = queue
+                    .jobs
+                    .values()
+                    .filter(|job| job.is_running() && job.heartbeat_age() > heartbeat_timeout)
+                    .map(|job| job.id)
+                    .collect();
+                for job_id in stale_ids {
+                    let job = queue
+                        .jobs
+                        .get_mut(&job_id)
+                        .expect("job_id was just collected from this queue's jobs");
+                    let requeued = job.requeue_or_fail(queue.settings.retries);
+                    if requeued {
+                        queue.pending.push(job_id);
+                    } else {
+                        queue.unique_keys.retain(|_, id| *id != job_id);
+                    }
+                    reclaimed.push(super::ReclaimedJob {
+                        queue_name: queue_name.clone(),
+                        job: job.clone(),
+                        requeued,
+                    });
+                }
+            }
+            Ok(reclaimed)
+        }
+
+        async fn set_job_attempt(&self, job_id: u64, attempt: u64) -> Result<(), OcyError> {
+            let mut queues = self.queues.lock().unwrap();
+            for queue in queues.values_mut() {
+                if let Some(job) = queue.jobs.get_mut(&job_id) {
+                    job.attempt = attempt;
+                    return Ok(());
+                }
+            }
+            Err(OcyError::NoSuchJob(job_id))
+        }
+    }
+}